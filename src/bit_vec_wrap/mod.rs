@@ -1,5 +1,6 @@
 extern crate bit_vec;
 use self::bit_vec::BitVec;
+use std::hash::{Hash, Hasher};
 
 // This is a wrapper around BitVec to implement methods not supported
 // directly by the bit_vec crate, in a very naive way.
@@ -8,9 +9,27 @@ use self::bit_vec::BitVec;
 // at arbitrary points in the vector. It can even be compressed! See
 // V. Mäkinen and G. Navarro. Dynamic entropy-compressed sequences and full-text indexes.
 
-#[derive(Clone, PartialEq, Debug, Eq, Hash)]
+#[derive(Clone, Debug)]
 pub struct BitVecWrap {
 	bit_vec: BitVec,
+	// cumulative popcount per 32-bit block, index[k] = number of 1-bits in blocks [0, k).
+	// None when not built, or stale after a mutation; see build_rank_index.
+	rank_index: Option<Vec<usize>>,
+}
+
+// the rank index is a cache, not part of the represented bitvector
+impl PartialEq for BitVecWrap {
+	fn eq(&self, other: &BitVecWrap) -> bool {
+		self.bit_vec == other.bit_vec
+	}
+}
+
+impl Eq for BitVecWrap {}
+
+impl Hash for BitVecWrap {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.bit_vec.hash(state);
+	}
 }
 
 impl BitVecWrap {
@@ -18,27 +37,31 @@ impl BitVecWrap {
 	// constructor
 	pub fn new() -> Self {
 		BitVecWrap {
-			bit_vec: BitVec::new()
+			bit_vec: BitVec::new(),
+			rank_index: None
 		}
 	}
 
 	// constructor
 	pub fn from_elem(nbits: usize, bit: bool) -> Self {
 		BitVecWrap {
-			bit_vec: BitVec::from_elem(nbits, bit)
+			bit_vec: BitVec::from_elem(nbits, bit),
+			rank_index: None
 		}
 	}
 
 	fn with_capacity(nbits: usize) -> Self {
 		BitVecWrap {
-			bit_vec: BitVec::with_capacity(nbits)
+			bit_vec: BitVec::with_capacity(nbits),
+			rank_index: None
 		}
 	}
 
 	// constructor
 	pub fn from_bytes(bytes: &[u8]) -> Self {
 		BitVecWrap {
-			bit_vec: BitVec::from_bytes(bytes)
+			bit_vec: BitVec::from_bytes(bytes),
+			rank_index: None
 		}
 	}
 
@@ -49,16 +72,20 @@ impl BitVecWrap {
 	// set a bit at index i
 	pub fn set(&mut self, i: usize, elem: bool) {
 		self.bit_vec.set(i, elem);
+		self.rank_index = None;
 	}
 
 	// add a bit at the end
 	pub fn push(&mut self, elem: bool) {
 		self.bit_vec.push(elem);
+		self.rank_index = None;
 	}
 
 	// remove the last bit and returns it. Returns None if the bitvector is empty.
 	pub fn pop(&mut self) -> Option<bool> {
-		self.bit_vec.pop()
+		let bit = self.bit_vec.pop();
+		self.rank_index = None;
+		bit
 	}
 
 	// insert a bit at index i, hereby shifting the bits after i one position towards the end
@@ -74,10 +101,12 @@ impl BitVecWrap {
 			}
 		}
 		self.bit_vec.set(i, elem);
+		self.rank_index = None;
 	}
 
 	pub fn append(&mut self, other: BitVecWrap) {
 		self.bit_vec.extend(other.bit_vec);
+		self.rank_index = None;
 	}
 
 	// delete a bit at index i, hereby shifting the bits after i one position towards the beginning
@@ -90,6 +119,26 @@ impl BitVecWrap {
 			}
 		}
 		self.bit_vec.pop();
+		self.rank_index = None;
+	}
+
+	// precompute the cumulative popcount per block, so that "rank_one" and "select"
+	// no longer have to fold over every block on every call. Call this once a
+	// bitvector is done growing (e.g. after "from_sequences" built a static trie);
+	// any later mutation invalidates the index again.
+	pub fn build_rank_index(&mut self) {
+		self.rank_index = Some(self.compute_rank_index());
+	}
+
+	fn compute_rank_index(&self) -> Vec<usize> {
+		let mut index = Vec::new();
+		let mut cumulative = 0;
+		index.push(cumulative);
+		for block in self.bit_vec.blocks() {
+			cumulative += block.count_ones() as usize;
+			index.push(cumulative);
+		}
+		index
 	}
 
 	// Number of ones in the vector before position "pos"
@@ -98,19 +147,22 @@ impl BitVecWrap {
 		if pos > self.bit_vec.len() {
 			panic!("Index out of bounds!");
 		}
-		let block_iter = self.bit_vec.blocks();
 		let low_pos = pos / 32; // 1 block = u32
 		let low_pos_rem = pos % 32;
 
-		// first count 1-bits up to low_pos
-		let mut bit_count = block_iter.take(low_pos).fold(0, |nr_bits, block| nr_bits + block.count_ones() as usize);
-
-		// now count the remaining bits up to the real position
-		let start_pos = pos - low_pos_rem;
-		for bit_pos in start_pos..pos {
-			match self.bit_vec.get(bit_pos) {
-				Some(true) => bit_count += 1,
-				_ => {}
+		// count 1-bits in the blocks before low_pos: an O(1) lookup if the rank
+		// index was built, otherwise fall back to folding over them
+		let mut bit_count = match self.rank_index {
+			Some(ref index) => index[low_pos],
+			None => self.bit_vec.blocks().take(low_pos).fold(0, |nr_bits, block| nr_bits + block.count_ones() as usize)
+		};
+
+		// now count the remaining bits up to the real position, by masking off
+		// the bits at or beyond low_pos_rem in the (at most one) partial block
+		if low_pos_rem > 0 {
+			if let Some(block) = self.bit_vec.blocks().nth(low_pos) {
+				let mask = (1u32 << low_pos_rem) - 1;
+				bit_count += (block & mask).count_ones() as usize;
 			}
 		}
 		bit_count
@@ -131,17 +183,76 @@ impl BitVecWrap {
 		}
 	}
 
+	// number of "bit"-bits among the blocks [0, block_nr), using the cumulative
+	// popcount index: ones are looked up directly, zeros are derived from the
+	// number of bits seen so far (all blocks are 32 bits wide, except possibly
+	// the last one, which may be shorter).
+	fn cumulative_rank(&self, index: &[usize], bit: bool, block_nr: usize) -> usize {
+		let ones = index[block_nr];
+		match bit {
+			true => ones,
+			false => {
+				let num_blocks = index.len() - 1;
+				let bits_seen = if block_nr < num_blocks { block_nr * 32 } else { self.len() };
+				bits_seen - ones
+			}
+		}
+	}
+
 	// Position (index) of occurrence_nr-th occurrence of bit. Starts at one!
 	pub fn select(&self, bit: bool, occurrence_nr: usize) -> Option<usize> {
-		// TODO OPTIMIZEME: can probably way more efficient with intrinsics, as in rank
-		let mut count = 0;
-		let pos = self.bit_vec.iter().position(|x| { 
-			if x == bit {
-				count = count + 1;
+		if occurrence_nr == 0 {
+			return None;
+		}
+		match self.rank_index {
+			// binary-search the cumulative counts for the block containing the
+			// occurrence_nr-th "bit", instead of scanning the whole bitvector
+			Some(ref index) => self.select_indexed(index, bit, occurrence_nr),
+			// no index built: fall back to a single non-allocating linear scan
+			// rather than paying for a one-off index just to throw it away
+			None => {
+				let mut count = 0;
+				self.bit_vec.iter().position(|x| {
+					if x == bit {
+						count += 1;
+					}
+					count == occurrence_nr
+				})
+			}
+		}
+	}
+
+	fn select_indexed(&self, index: &[usize], bit: bool, occurrence_nr: usize) -> Option<usize> {
+		let num_blocks = index.len() - 1;
+
+		let mut low = 0;
+		let mut high = num_blocks;
+		while low < high {
+			let mid = low + (high - low) / 2;
+			if self.cumulative_rank(index, bit, mid + 1) < occurrence_nr {
+				low = mid + 1;
+			} else {
+				high = mid;
 			}
-			count == occurrence_nr
-		});
-		pos
+		}
+		if low == num_blocks {
+			return None;
+		}
+		let block_nr = low;
+
+		// scan only within that block for the exact position
+		let mut count = self.cumulative_rank(index, bit, block_nr);
+		let block_start = block_nr * 32;
+		let block_end = (block_start + 32).min(self.len());
+		for pos in block_start..block_end {
+			if self.bit_vec.get(pos) == Some(bit) {
+				count += 1;
+				if count == occurrence_nr {
+					return Some(pos);
+				}
+			}
+		}
+		None
 	}
 
 	pub fn is_empty(&self) -> bool {
@@ -154,6 +265,7 @@ impl BitVecWrap {
 
 	pub fn truncate(&mut self, len: usize) {
 		self.bit_vec.truncate(len);
+		self.rank_index = None;
 	}
 
 	pub fn to_bytes(&self) -> Vec<u8> {
@@ -249,6 +361,7 @@ impl BitVecWrap {
 	pub fn set_none(&mut self) {
 		self.bit_vec.set_all();
 		self.bit_vec.negate();
+		self.rank_index = None;
 	}
 
 }
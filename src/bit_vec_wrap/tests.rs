@@ -34,6 +34,43 @@ mod tests {
 		assert_eq!(4, bv.rank_zero(75));
 	}
 
+	#[test]
+	fn build_rank_index() {
+		let mut bv = BitVecWrap::from_elem(75, false);
+		bv.set(4, true);
+		bv.set(5, true);
+		bv.set(35, true);
+		bv.set(74, true);
+		bv.build_rank_index();
+		assert_eq!(0, bv.rank_one(0));
+		assert_eq!(0, bv.rank_one(4));
+		assert_eq!(1, bv.rank_one(5));
+		assert_eq!(2, bv.rank_one(6));
+		assert_eq!(2, bv.rank_one(33));
+		assert_eq!(3, bv.rank_one(36));
+		assert_eq!(4, bv.rank_one(75));
+	}
+
+	#[test]
+	fn select_one() {
+		let mut bv = BitVecWrap::from_elem(75, false);
+		bv.set(4, true);
+		bv.set(5, true);
+		bv.set(35, true);
+		bv.set(74, true);
+		assert_eq!(Some(4), bv.select(true, 1));
+		assert_eq!(Some(5), bv.select(true, 2));
+		assert_eq!(Some(35), bv.select(true, 3));
+		assert_eq!(Some(74), bv.select(true, 4));
+		assert_eq!(None, bv.select(true, 5));
+		assert_eq!(Some(0), bv.select(false, 1));
+
+		// a mutation after building the rank index must invalidate it
+		bv.build_rank_index();
+		bv.set(6, true);
+		assert_eq!(Some(6), bv.select(true, 3));
+	}
+
 	#[test]
 	fn insert() {
 		let mut bv = BitVecWrap::new();
@@ -30,9 +30,28 @@ impl WaveletTrie {
 	pub fn from_sequences(sequences: &[BitVecWrap]) -> Self {
 		let mut wavelet_trie = WaveletTrie::new();
 		wavelet_trie.insert_static(sequences);
+		// this path never mutates again, so build the rank index once up front
+		wavelet_trie.freeze();
 		wavelet_trie
 	}
 
+	// precompute the rank index (see BitVecWrap::build_rank_index) on "positions"
+	// at every node, so that "rank"/"access" descend in O(1) per level instead of
+	// O(n/32), and "select" binary-searches the blocks instead of scanning them.
+	// "insert" changes "positions" before its rank lookup at each level, so it does
+	// not profit from the index. Call this after a burst of insert/append/delete
+	// calls on a trie that is about to be queried heavily; any later mutation
+	// invalidates the index of the nodes it touches again.
+	pub fn freeze(&mut self) {
+		self.positions.build_rank_index();
+		if let Some(ref mut left) = self.left {
+			left.freeze();
+		}
+		if let Some(ref mut right) = self.right {
+			right.freeze();
+		}
+	}
+
 	fn insert_static(&mut self, sequences: &[BitVecWrap]) {
 		if !sequences.is_empty() {
 			// first check if all bitvectors in the sequence are the same
@@ -250,10 +269,329 @@ impl WaveletTrie {
 		}
 	}
 
+	// position in the original sequence list of the "occurrence_nr"-th (starts at one!)
+	// string that has "sequence" as a prefix.
+	// this is the inverse of "rank": "rank" descends translating a position downwards
+	// through "positions", "select" climbs back up translating it through "positions" as well,
+	// but using BitVecWrap::select instead of BitVecWrap::rank.
+	// returns None if fewer than "occurrence_nr" occurrences exist
+	pub fn select(&self, sequence: &BitVecWrap, occurrence_nr: usize) -> Option<usize> {
+		if occurrence_nr == 0 || occurrence_nr > self.len() {
+			None
+		} else if sequence.is_empty() || sequence == &self.prefix {
+			Some(occurrence_nr - 1)
+		} else if sequence.len() < self.prefix.len() {
+			// sequence has to be a prefix of "prefix"
+			match sequence.is_prefix_of(&self.prefix) {
+				true => Some(occurrence_nr - 1),
+				false => None
+			}
+		} else {
+			// "prefix" has to be a prefix of sequence
+			match self.prefix.is_prefix_of(sequence) {
+				true => {
+					let (bit, suffix) = sequence.different_suffix(self.prefix.len());
+					match bit {
+						true => {
+							match self.right {
+								Some(ref trie) => match trie.select(&suffix, occurrence_nr) {
+									Some(child_pos) => self.positions.select(true, child_pos + 1),
+									None => None
+								},
+								None => self.positions.select(true, occurrence_nr)
+							}
+						},
+						false => {
+							match self.left {
+								Some(ref trie) => match trie.select(&suffix, occurrence_nr) {
+									Some(child_pos) => self.positions.select(false, child_pos + 1),
+									None => None
+								},
+								None => self.positions.select(false, occurrence_nr)
+							}
+						}
+					}
+				},
+				false => None
+			}
+		}
+	}
+
+	// every distinct full string stored in the trie that begins with "prefix"
+	pub fn distinct_with_prefix(&self, prefix: &BitVecWrap) -> Vec<BitVecWrap> {
+		// an empty trie holds no sequences at all, so even the empty prefix
+		// must not match the empty root and produce a phantom string
+		if self.len() == 0 {
+			return Vec::new();
+		}
+		match self.find_subtrie(prefix, BitVecWrap::new()) {
+			Some((subtrie, path)) => subtrie.collect_distinct(path),
+			None => Vec::new()
+		}
+	}
+
+	// descend to the subtrie corresponding to "prefix", exactly as "rank" does,
+	// accumulating the path (node prefixes and branch bits) followed along the way
+	fn find_subtrie<'a>(&'a self, prefix: &BitVecWrap, path: BitVecWrap) -> Option<(&'a WaveletTrie, BitVecWrap)> {
+		if prefix.is_empty() || prefix == &self.prefix {
+			Some((self, path))
+		} else if prefix.len() < self.prefix.len() {
+			// prefix has to be a prefix of "prefix" (the node's one)
+			match prefix.is_prefix_of(&self.prefix) {
+				true => Some((self, path)),
+				false => None
+			}
+		} else {
+			// "prefix" (the node's one) has to be a prefix of prefix
+			match self.prefix.is_prefix_of(prefix) {
+				true => {
+					let (bit, suffix) = prefix.different_suffix(self.prefix.len());
+					let mut new_path = path;
+					new_path.append(self.prefix.copy());
+					new_path.push(bit);
+					match bit {
+						true => match self.right {
+							Some(ref trie) => trie.find_subtrie(&suffix, new_path),
+							None => None
+						},
+						false => match self.left {
+							Some(ref trie) => trie.find_subtrie(&suffix, new_path),
+							None => None
+						}
+					}
+				},
+				false => None
+			}
+		}
+	}
+
+	// DFS over this subtrie, collecting node.prefix ++ branch_bit ++ child.prefix ... down
+	// every root-to-leaf path, prepending the accumulated "path" from the descent
+	fn collect_distinct(&self, path: BitVecWrap) -> Vec<BitVecWrap> {
+		let mut current = path;
+		current.append(self.prefix.copy());
+		if self.left.is_none() && self.right.is_none() {
+			return vec![current];
+		}
+		let mut result = Vec::new();
+		if let Some(ref left) = self.left {
+			let mut left_path = current.copy();
+			left_path.push(false);
+			result.extend(left.collect_distinct(left_path));
+		}
+		if let Some(ref right) = self.right {
+			let mut right_path = current.copy();
+			right_path.push(true);
+			result.extend(right.collect_distinct(right_path));
+		}
+		result
+	}
+
+	// remove the string at "index", the dynamic counterpart to "insert"
+	pub fn delete(&mut self, index: usize) -> Result<(), &'static str> {
+		if index >= self.len() {
+			return Err("Index out of bounds.");
+		}
+		// leaf: just drop the position, there is nothing to recurse into
+		if self.left.is_none() && self.right.is_none() {
+			self.positions.delete(index);
+			return Ok(());
+		}
+		let bit = self.positions.get(index).unwrap();
+		let result = match bit {
+			true => {
+				if let Some(ref mut child) = self.right {
+					let new_index = self.positions.rank_one(index);
+					child.delete(new_index)
+				} else {
+					Err("The right child has run away!")
+				}
+			},
+			false => {
+				if let Some(ref mut child) = self.left {
+					let new_index = self.positions.rank_zero(index);
+					child.delete(new_index)
+				} else {
+					Err("The left child has run away!")
+				}
+			}
+		};
+		result?;
+		self.positions.delete(index);
+		self.collapse();
+		Ok(())
+	}
+
+	// if exactly one child is left with any sequences in it, merge it back into this
+	// node, restoring the invariant that every internal node has two real subtries
+	fn collapse(&mut self) {
+		let left_empty = match self.left {
+			Some(ref child) => child.len() == 0,
+			None => true
+		};
+		let right_empty = match self.right {
+			Some(ref child) => child.len() == 0,
+			None => true
+		};
+		if left_empty && !right_empty {
+			if let Some(child) = self.right.take() {
+				let child = *child;
+				let mut new_prefix = self.prefix.copy();
+				new_prefix.push(true);
+				new_prefix.append(child.prefix);
+				self.prefix = new_prefix;
+				self.positions = child.positions;
+				self.left = child.left;
+				self.right = child.right;
+			}
+		} else if right_empty && !left_empty {
+			if let Some(child) = self.left.take() {
+				let child = *child;
+				let mut new_prefix = self.prefix.copy();
+				new_prefix.push(false);
+				new_prefix.append(child.prefix);
+				self.prefix = new_prefix;
+				self.positions = child.positions;
+				self.left = child.left;
+				self.right = child.right;
+			}
+		}
+	}
+
+	// encode this trie as a compact pre-order traversal: for every node, the bit-length
+	// and packed bytes of "prefix" and "positions", followed by a one-byte flag marking
+	// which children are present, followed recursively by those children.
+	pub fn serialize(&self) -> Vec<u8> {
+		let mut bytes = Vec::new();
+		self.serialize_into(&mut bytes);
+		bytes
+	}
+
+	fn serialize_into(&self, bytes: &mut Vec<u8>) {
+		write_bitvec(bytes, &self.prefix);
+		write_bitvec(bytes, &self.positions);
+		let flag: u8 = match (self.left.is_some(), self.right.is_some()) {
+			(false, false) => 0,
+			(true, false) => 1,
+			(false, true) => 2,
+			(true, true) => 3
+		};
+		bytes.push(flag);
+		if let Some(ref left) = self.left {
+			left.serialize_into(bytes);
+		}
+		if let Some(ref right) = self.right {
+			right.serialize_into(bytes);
+		}
+	}
+
+	// reconstruct a trie previously produced by "serialize"
+	pub fn deserialize(bytes: &[u8]) -> Result<WaveletTrie, &'static str> {
+		let mut pos = 0;
+		WaveletTrie::deserialize_from(bytes, &mut pos)
+	}
+
+	fn deserialize_from(bytes: &[u8], pos: &mut usize) -> Result<WaveletTrie, &'static str> {
+		let prefix = match read_bitvec(bytes, pos) {
+			Some(bit_vec) => bit_vec,
+			None => return Err("Unexpected end of input while reading a node's prefix.")
+		};
+		let positions = match read_bitvec(bytes, pos) {
+			Some(bit_vec) => bit_vec,
+			None => return Err("Unexpected end of input while reading a node's positions.")
+		};
+		if *pos >= bytes.len() {
+			return Err("Unexpected end of input while reading a node's child flag.");
+		}
+		let flag = bytes[*pos];
+		*pos += 1;
+
+		let left = if flag & 1 != 0 {
+			match WaveletTrie::deserialize_from(bytes, pos) {
+				Ok(trie) => Some(Box::new(trie)),
+				Err(message) => return Err(message)
+			}
+		} else {
+			None
+		};
+		let right = if flag & 2 != 0 {
+			match WaveletTrie::deserialize_from(bytes, pos) {
+				Ok(trie) => Some(Box::new(trie)),
+				Err(message) => return Err(message)
+			}
+		} else {
+			None
+		};
+
+		Ok(WaveletTrie { prefix, positions, left, right })
+	}
+
+	// reconstruct the string stored at "index"
+	// returns None if index is out of bounds
+	pub fn access(&self, index: usize) -> Option<BitVecWrap> {
+		if index >= self.len() {
+			None
+		} else {
+			Some(self.access_unchecked(index))
+		}
+	}
+
+	fn access_unchecked(&self, index: usize) -> BitVecWrap {
+		let mut result = self.prefix.copy();
+		// a leaf has no children: the prefix is the whole story
+		if self.left.is_none() && self.right.is_none() {
+			return result;
+		}
+		let bit = self.positions.get(index).unwrap();
+		result.push(bit);
+		match bit {
+			true => {
+				let new_index = self.positions.rank_one(index);
+				if let Some(ref trie) = self.right {
+					result.append(trie.access_unchecked(new_index));
+				}
+			},
+			false => {
+				let new_index = self.positions.rank_zero(index);
+				if let Some(ref trie) = self.left {
+					result.append(trie.access_unchecked(new_index));
+				}
+			}
+		}
+		result
+	}
+
 	// the number of sequences contained in this trie
 	pub fn len(&self) -> usize {
 		self.positions.len()
 	}
 }
 
+// "to_bytes" pads to a byte boundary, so the bit length has to be stored alongside
+// the packed bytes to know where the real content ends.
+fn write_bitvec(bytes: &mut Vec<u8>, bit_vec: &BitVecWrap) {
+	let bit_len = bit_vec.len() as u64;
+	bytes.extend_from_slice(&bit_len.to_le_bytes());
+	bytes.extend_from_slice(&bit_vec.to_bytes());
+}
+
+fn read_bitvec(bytes: &[u8], pos: &mut usize) -> Option<BitVecWrap> {
+	if *pos + 8 > bytes.len() {
+		return None;
+	}
+	let mut bit_len_bytes = [0u8; 8];
+	bit_len_bytes.copy_from_slice(&bytes[*pos..*pos + 8]);
+	let bit_len = u64::from_le_bytes(bit_len_bytes) as usize;
+	*pos += 8;
+
+	let byte_len = bit_len.div_ceil(8);
+	if *pos + byte_len > bytes.len() {
+		return None;
+	}
+	let mut bit_vec = BitVecWrap::from_bytes(&bytes[*pos..*pos + byte_len]);
+	*pos += byte_len;
+	bit_vec.truncate(bit_len);
+	Some(bit_vec)
+}
+
 mod tests;
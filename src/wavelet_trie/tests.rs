@@ -0,0 +1,174 @@
+#[cfg(test)]
+mod tests {
+	use wavelet_trie::WaveletTrie;
+	use bit_vec_wrap::BitVecWrap;
+
+	fn bv(bits: &[bool]) -> BitVecWrap {
+		let mut bit_vec = BitVecWrap::new();
+		for &bit in bits {
+			bit_vec.push(bit);
+		}
+		bit_vec
+	}
+
+	#[test]
+	fn access() {
+		let sequences = vec![
+			bv(&[false, false, false]),
+			bv(&[false, true, false]),
+			bv(&[true, true, true]),
+		];
+		let trie = WaveletTrie::from_sequences(&sequences);
+		for (index, sequence) in sequences.iter().enumerate() {
+			assert_eq!(Some(sequence.clone()), trie.access(index));
+		}
+		assert_eq!(None, trie.access(sequences.len()));
+	}
+
+	#[test]
+	fn select() {
+		let sequences = vec![
+			bv(&[false, false, false]),
+			bv(&[false, true, false]),
+			bv(&[true, true, true]),
+		];
+		let trie = WaveletTrie::from_sequences(&sequences);
+		for (index, sequence) in sequences.iter().enumerate() {
+			assert_eq!(Some(index), trie.select(sequence, 1));
+		}
+		// only one occurrence of each sequence
+		assert_eq!(None, trie.select(&sequences[0], 2));
+		// no sequence in the trie starts with this prefix
+		assert_eq!(None, trie.select(&bv(&[true, false]), 1));
+	}
+
+	#[test]
+	fn select_with_duplicates() {
+		let sequences = vec![
+			bv(&[false, false, false]),
+			bv(&[false, true, false]),
+			bv(&[false, false, false]),
+			bv(&[true, true, true]),
+			bv(&[false, true, false]),
+		];
+		let trie = WaveletTrie::from_sequences(&sequences);
+
+		// [false, false, false] occurs at indices 0 and 2
+		assert_eq!(Some(0), trie.select(&sequences[0], 1));
+		assert_eq!(Some(2), trie.select(&sequences[0], 2));
+		assert_eq!(None, trie.select(&sequences[0], 3));
+
+		// [false, true, false] occurs at indices 1 and 4
+		assert_eq!(Some(1), trie.select(&sequences[1], 1));
+		assert_eq!(Some(4), trie.select(&sequences[1], 2));
+		assert_eq!(None, trie.select(&sequences[1], 3));
+
+		// the shared prefix [false] occurs at indices 0, 1, 2 and 4, in that order
+		let prefix = bv(&[false]);
+		assert_eq!(Some(0), trie.select(&prefix, 1));
+		assert_eq!(Some(1), trie.select(&prefix, 2));
+		assert_eq!(Some(2), trie.select(&prefix, 3));
+		assert_eq!(Some(4), trie.select(&prefix, 4));
+		assert_eq!(None, trie.select(&prefix, 5));
+	}
+
+	#[test]
+	fn distinct_with_prefix() {
+		let sequences = vec![
+			bv(&[false, false, false]),
+			bv(&[false, true, false]),
+			bv(&[true, true, true]),
+		];
+		let trie = WaveletTrie::from_sequences(&sequences);
+
+		assert_eq!(sequences.clone(), trie.distinct_with_prefix(&BitVecWrap::new()));
+
+		let starting_with_false = trie.distinct_with_prefix(&bv(&[false]));
+		assert_eq!(vec![sequences[0].clone(), sequences[1].clone()], starting_with_false);
+
+		assert_eq!(Vec::<BitVecWrap>::new(), trie.distinct_with_prefix(&bv(&[true, false])));
+	}
+
+	#[test]
+	fn distinct_with_prefix_empty_trie() {
+		let trie = WaveletTrie::from_sequences(&[]);
+		// a trie with no sequences has no distinct strings, not even the empty one
+		assert_eq!(Vec::<BitVecWrap>::new(), trie.distinct_with_prefix(&BitVecWrap::new()));
+	}
+
+	#[test]
+	fn delete() {
+		let sequences = vec![
+			bv(&[false, false, false]),
+			bv(&[false, true, false]),
+			bv(&[true, true, true]),
+		];
+		let mut trie = WaveletTrie::from_sequences(&sequences);
+
+		assert_eq!(Ok(()), trie.delete(0));
+		assert_eq!(2, trie.len());
+		assert_eq!(Some(sequences[1].clone()), trie.access(0));
+		assert_eq!(Some(sequences[2].clone()), trie.access(1));
+
+		assert!(trie.delete(trie.len()).is_err());
+	}
+
+	#[test]
+	fn delete_duplicate() {
+		let sequences = vec![
+			bv(&[false, false, false]),
+			bv(&[false, true, false]),
+			bv(&[false, false, false]),
+			bv(&[true, true, true]),
+		];
+		let mut trie = WaveletTrie::from_sequences(&sequences);
+
+		// delete the second occurrence of the duplicated sequence
+		assert_eq!(Ok(()), trie.delete(2));
+		assert_eq!(3, trie.len());
+		assert_eq!(Some(sequences[0].clone()), trie.access(0));
+		assert_eq!(Some(sequences[1].clone()), trie.access(1));
+		assert_eq!(Some(sequences[3].clone()), trie.access(2));
+
+		// only the first occurrence remains
+		assert_eq!(Some(0), trie.select(&sequences[0], 1));
+		assert_eq!(None, trie.select(&sequences[0], 2));
+	}
+
+	#[test]
+	fn delete_collapses_single_child() {
+		let sequences = vec![
+			bv(&[false, false]),
+			bv(&[false, true]),
+			bv(&[true]),
+		];
+		let mut trie = WaveletTrie::from_sequences(&sequences);
+
+		// deleting the "true" leaf leaves its sibling as the only child of the
+		// root, which must be collapsed back into the root (see "collapse")
+		assert_eq!(Ok(()), trie.delete(2));
+		assert_eq!(2, trie.len());
+		assert_eq!(Some(sequences[0].clone()), trie.access(0));
+		assert_eq!(Some(sequences[1].clone()), trie.access(1));
+	}
+
+	#[test]
+	fn serialize_roundtrip() {
+		let sequences = vec![
+			bv(&[false, false, false]),
+			bv(&[false, true, false]),
+			bv(&[true, true, true]),
+		];
+		let trie = WaveletTrie::from_sequences(&sequences);
+
+		let bytes = trie.serialize();
+		let restored = WaveletTrie::deserialize(&bytes).unwrap();
+
+		assert_eq!(trie.len(), restored.len());
+		for (index, sequence) in sequences.iter().enumerate() {
+			assert_eq!(Some(sequence.clone()), restored.access(index));
+		}
+
+		assert!(WaveletTrie::deserialize(&[]).is_err());
+	}
+}